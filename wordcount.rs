@@ -1,11 +1,12 @@
 // wordcount.rs - Word frequency counter
 // Build: rustc -O wordcount.rs -o wordcount_rust
-// Usage: ./wordcount_rust [filename]
+// Usage: ./wordcount_rust [filename ...]
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::env;
 use std::fs::File;
-use std::io::{self, Read, Write, BufWriter};
+use std::io::{self, BufRead, BufReader, Read, Write, BufWriter};
+use std::thread;
 use std::time::Instant;
 
 // FNV-1a hash
@@ -35,80 +36,713 @@ impl std::hash::Hasher for FnvHasher {
 
 type FnvBuildHasher = std::hash::BuildHasherDefault<FnvHasher>;
 type FnvHashMap<K, V> = HashMap<K, V, FnvBuildHasher>;
+type FnvHashSet<T> = HashSet<T, FnvBuildHasher>;
+
+// Built-in stopword lists, selected with `--stopwords <lang>`.
+const ENGLISH_STOPWORDS: &[&str] = &[
+    "a", "an", "the", "and", "or", "but", "if", "of", "at", "by", "for",
+    "with", "about", "against", "between", "into", "through", "during",
+    "before", "after", "above", "below", "to", "from", "up", "down", "in",
+    "out", "on", "off", "over", "under", "again", "further", "then", "once",
+    "is", "am", "are", "was", "were", "be", "been", "being", "have", "has",
+    "had", "having", "do", "does", "did", "doing", "will", "would", "should",
+    "can", "could", "may", "might", "must", "shall", "i", "me", "my",
+    "myself", "we", "our", "ours", "ourselves", "you", "your", "yours",
+    "he", "him", "his", "himself", "she", "her", "hers", "herself", "it",
+    "its", "itself", "they", "them", "their", "theirs", "themselves",
+    "what", "which", "who", "whom", "this", "that", "these", "those", "not",
+    "no", "nor", "so", "than", "too", "very", "s", "t", "just", "as",
+];
+
+/// Output file format selected with `--format`. Defaults to `Text`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Text,
+    Json,
+    Csv,
+}
+
+impl OutputFormat {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(OutputFormat::Text),
+            "json" => Some(OutputFormat::Json),
+            "csv" => Some(OutputFormat::Csv),
+            _ => None,
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Text => "txt",
+            OutputFormat::Json => "json",
+            OutputFormat::Csv => "csv",
+        }
+    }
+}
+
+/// A word's aggregate `(frequency, document_frequency)` across all input
+/// files: total occurrences, and the number of files it appeared in at
+/// least once (mirroring MeiliSearch's most-common-words report).
+type WordStat = (u32, u32);
+
+/// Run-wide statistics, gathered across every input file, that the output
+/// writers render alongside the word ranking.
+struct Stats {
+    file_count: usize,
+    total_size_mb: f64,
+    total_words: u64,
+    unique_words: usize,
+    filtered_words: u64,
+    execution_time_ms: f64,
+    readability: Option<ReadabilityScores>,
+    unicode: bool,
+}
+
+/// Flesch readability scores, computed from the same pass as word counting
+/// when `--readability` is given.
+struct ReadabilityScores {
+    sentences: u64,
+    syllables: u64,
+    flesch_reading_ease: f64,
+    flesch_kincaid_grade: f64,
+}
+
+impl ReadabilityScores {
+    /// Returns `None` if there are no detected sentences, since both scores
+    /// divide by sentence count.
+    fn compute(total_words: u64, sentences: u64, syllables: u64) -> Option<Self> {
+        if sentences == 0 || total_words == 0 {
+            return None;
+        }
+
+        let words = total_words as f64;
+        let sentences_f = sentences as f64;
+        let syllables_f = syllables as f64;
+
+        let flesch_reading_ease =
+            206.835 - 1.015 * (words / sentences_f) - 84.6 * (syllables_f / words);
+        let flesch_kincaid_grade =
+            0.39 * (words / sentences_f) + 11.8 * (syllables_f / words) - 15.59;
+
+        Some(ReadabilityScores {
+            sentences,
+            syllables,
+            flesch_reading_ease,
+            flesch_kincaid_grade,
+        })
+    }
+}
+
+/// Estimates syllables in a lowercased word with the classic heuristic:
+/// one syllable per run of consecutive vowels (a, e, i, o, u, y), minus one
+/// for a silent trailing "e", clamped to a minimum of 1.
+fn estimate_syllables(word: &str) -> u32 {
+    let mut groups = 0u32;
+    let mut in_vowel_run = false;
+
+    for ch in word.chars() {
+        let is_vowel = matches!(ch, 'a' | 'e' | 'i' | 'o' | 'u' | 'y');
+        if is_vowel && !in_vowel_run {
+            groups += 1;
+        }
+        in_vowel_run = is_vowel;
+    }
+
+    if word.ends_with('e') && groups > 0 {
+        groups -= 1;
+    }
+
+    groups.max(1)
+}
 
 fn main() -> io::Result<()> {
     let args: Vec<String> = env::args().collect();
-    let filename = args.get(1).map(|s| s.as_str()).unwrap_or("book.txt");
-    
-    println!("Processing file: {}", filename);
-    
-    let start_time = Instant::now();
-    
-    let mut file = match File::open(filename) {
-        Ok(f) => f,
-        Err(e) => {
-            eprintln!("Error: File '{}' not found", filename);
-            eprintln!("Error details: {}", e);
-            std::process::exit(1);
+    let mut filenames: Vec<String> = Vec::new();
+    let mut threads = 1usize;
+    let mut stopwords_lang: Option<String> = None;
+    let mut stopwords_file: Option<String> = None;
+    let mut ngram_n: Option<usize> = None;
+    let mut format = OutputFormat::Text;
+    let mut readability = false;
+    let mut unicode = false;
+    let mut query_words_list: Vec<String> = Vec::new();
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--threads" => {
+                i += 1;
+                threads = args
+                    .get(i)
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .unwrap_or(1)
+                    .max(1);
+            }
+            "--stopwords" => {
+                i += 1;
+                stopwords_lang = args.get(i).cloned();
+            }
+            "--stopwords-file" => {
+                i += 1;
+                stopwords_file = args.get(i).cloned();
+            }
+            "--ngram" => {
+                i += 1;
+                ngram_n = args.get(i).and_then(|n| n.parse::<usize>().ok());
+                if ngram_n.is_some_and(|n| n < 2) {
+                    eprintln!("Error: --ngram requires a value of 2 or more");
+                    std::process::exit(1);
+                }
+            }
+            "--format" => {
+                i += 1;
+                format = match args.get(i).and_then(|f| OutputFormat::parse(f)) {
+                    Some(format) => format,
+                    None => {
+                        eprintln!("Error: --format must be one of text, json, csv");
+                        std::process::exit(1);
+                    }
+                };
+            }
+            "--readability" => readability = true,
+            "--unicode" => unicode = true,
+            "--words" => {
+                i += 1;
+                if let Some(list) = args.get(i) {
+                    query_words_list.extend(
+                        list.split(',')
+                            .map(|w| w.trim().to_lowercase())
+                            .filter(|w| !w.is_empty()),
+                    );
+                }
+            }
+            other => filenames.push(other.to_string()),
         }
-    };
-    
-    let mut buffer = Vec::new();
-    file.read_to_end(&mut buffer)?;
-    
-    let file_size = buffer.len() as f64 / 1024.0 / 1024.0;
-    
-    let mut counts: FnvHashMap<String, u32> = FnvHashMap::with_capacity_and_hasher(
-        10_000, 
+        i += 1;
+    }
+    if filenames.is_empty() {
+        filenames.push("book.txt".to_string());
+    }
+
+    let stopwords = load_stopwords(stopwords_lang.as_deref(), stopwords_file.as_deref())?;
+
+    let start_time = Instant::now();
+
+    let mut global_counts: FnvHashMap<String, WordStat> = FnvHashMap::with_capacity_and_hasher(
+        10_000,
         FnvBuildHasher::default()
     );
-    
+    let mut global_ngrams: FnvHashMap<Vec<String>, u32> = FnvHashMap::default();
+    let mut total_size_mb = 0.0f64;
     let mut total_words = 0u64;
-    let mut current_word = Vec::with_capacity(100);
-    
-    for &byte in buffer.iter() {
-        if byte.is_ascii_alphabetic() {
-            current_word.push(byte.to_ascii_lowercase());
-        } else if !current_word.is_empty() {
-            let word = unsafe { String::from_utf8_unchecked(current_word.clone()) };
-            
-            *counts.entry(word).or_insert(0) += 1;
-            total_words += 1;
-            current_word.clear();
+    let mut filtered_words = 0u64;
+    let mut total_sentences = 0u64;
+    let mut total_syllables = 0u64;
+    let mut ran_parallel = false;
+
+    for filename in &filenames {
+        println!("Processing file: {}", filename);
+
+        let mut file = match File::open(filename) {
+            Ok(f) => f,
+            Err(e) => {
+                eprintln!("Error: File '{}' not found", filename);
+                eprintln!("Error details: {}", e);
+                std::process::exit(1);
+            }
+        };
+
+        let mut buffer = Vec::new();
+        file.read_to_end(&mut buffer)?;
+        total_size_mb += buffer.len() as f64 / 1024.0 / 1024.0;
+
+        let result = if unicode {
+            if threads > 1 {
+                eprintln!("Note: --unicode runs single-threaded; ignoring --threads");
+            }
+            let text = String::from_utf8_lossy(&buffer);
+            count_chunk_unicode(&text, stopwords.as_ref(), ngram_n, readability)
+        } else if threads > 1 && ngram_n.is_some() {
+            // Each worker's ring buffer starts empty, so a word pair straddling
+            // a chunk boundary would be silently dropped; run serially instead
+            // of reporting wrong n-gram counts.
+            eprintln!("Note: --ngram runs single-threaded; ignoring --threads");
+            count_chunk(&buffer, stopwords.as_ref(), ngram_n, readability)
+        } else if threads > 1 && readability {
+            // Each worker's terminator-run state resets at its chunk start, so
+            // a sentence terminator straddling a cut is double-counted; run
+            // serially instead of reporting wrong readability scores.
+            eprintln!("Note: --readability runs single-threaded; ignoring --threads");
+            count_chunk(&buffer, stopwords.as_ref(), ngram_n, readability)
+        } else if threads > 1 {
+            ran_parallel = true;
+            count_words_parallel(&buffer, threads, stopwords.as_ref(), ngram_n, readability)
+        } else {
+            count_chunk(&buffer, stopwords.as_ref(), ngram_n, readability)
+        };
+
+        total_words += result.total_words;
+        filtered_words += result.filtered_words;
+        total_sentences += result.total_sentences;
+        total_syllables += result.total_syllables;
+
+        for (pair, count) in result.ngrams {
+            *global_ngrams.entry(pair).or_insert(0) += count;
+        }
+
+        // `result.counts` already holds each word at most once per file (the
+        // per-file tokenizer loop dedupes into it), so merging it here bumps
+        // document_frequency by exactly one per file, with no extra per-file
+        // seen-set needed.
+        for (word, count) in result.counts {
+            let stat = global_counts.entry(word).or_insert((0, 0));
+            stat.0 += count;
+            stat.1 += 1;
         }
     }
-    
-    if !current_word.is_empty() {
-        let word = unsafe { String::from_utf8_unchecked(current_word) };
-        *counts.entry(word).or_insert(0) += 1;
-        total_words += 1;
-    }
-    
-    let mut sorted: Vec<(&String, &u32)> = counts.iter().collect();
+
+    let mut sorted: Vec<(&String, &WordStat)> = global_counts.iter().collect();
     sorted.sort_unstable_by(|a, b| {
+        b.1.0.cmp(&a.1.0).then_with(|| a.0.cmp(b.0))
+    });
+
+    let mut sorted_ngrams: Vec<(&Vec<String>, &u32)> = global_ngrams.iter().collect();
+    sorted_ngrams.sort_unstable_by(|a, b| {
         b.1.cmp(a.1).then_with(|| a.0.cmp(b.0))
     });
-    
+
+    let query_results = query_words(&query_words_list, &global_counts, &sorted, total_words);
+
     let duration = start_time.elapsed();
     let execution_time = duration.as_secs_f64() * 1000.0;
-    
+
     println!("\n=== Top 10 Most Frequent Words ===");
-    for (index, (word, count)) in sorted.iter().take(10).enumerate() {
-        println!("{:2}. {:<15} {:>8}", index + 1, word, format_number(**count));
+    for (index, (word, stat)) in sorted.iter().take(10).enumerate() {
+        println!(
+            "{:2}. {:<15} {:>8}  (in {} file{})",
+            index + 1,
+            word,
+            format_number(stat.0),
+            stat.1,
+            if stat.1 == 1 { "" } else { "s" }
+        );
     }
-    
+
+    if let Some(n) = ngram_n {
+        println!("\n=== Top 10 Word Pairs ({}-grams) ===", n);
+        for (index, (words, count)) in sorted_ngrams.iter().take(10).enumerate() {
+            println!("{:2}. {:<25} {:>8}", index + 1, words.join(" "), format_number(**count));
+        }
+    }
+
+    if !query_results.is_empty() {
+        println!("\n=== Word Query ===");
+        for q in &query_results {
+            match q.rank {
+                Some(rank) => println!(
+                    "{:<15} {:>8}  (rank #{}, {:.2}%)",
+                    q.word,
+                    format_number(q.count),
+                    rank,
+                    q.percentage
+                ),
+                None => println!("{:<15} {:>8}  (not found)", q.word, format_number(q.count)),
+            }
+        }
+    }
+
+    let stats = Stats {
+        file_count: filenames.len(),
+        total_size_mb,
+        total_words,
+        unique_words: global_counts.len(),
+        filtered_words,
+        execution_time_ms: execution_time,
+        readability: if readability {
+            ReadabilityScores::compute(total_words, total_sentences, total_syllables)
+        } else {
+            None
+        },
+        unicode,
+    };
+
+    if readability {
+        println!("\n=== Readability ===");
+        match &stats.readability {
+            Some(r) => {
+                println!("Sentences:             {}", format_number(r.sentences as u32));
+                println!("Syllables:              {}", format_number(r.syllables as u32));
+                println!("Flesch Reading Ease:   {:.2}", r.flesch_reading_ease);
+                println!("Flesch-Kincaid Grade:  {:.2}", r.flesch_kincaid_grade);
+            }
+            None => println!("Not enough sentence-ending punctuation to compute readability scores."),
+        }
+    }
+
     println!("\n=== Statistics ===");
-    println!("File size:       {:.2} MB", file_size);
-    println!("Total words:     {}", format_number(total_words as u32));
-    println!("Unique words:    {}", format_number(counts.len() as u32));
-    println!("Execution time:  {:.2} ms", execution_time);
+    println!("Files processed: {}", stats.file_count);
+    println!("Total size:      {:.2} MB", stats.total_size_mb);
+    println!("Total words:     {}", format_number(stats.total_words as u32));
+    println!("Unique words:    {}", format_number(stats.unique_words as u32));
+    println!("Execution time:  {:.2} ms", stats.execution_time_ms);
     println!("Hash function:   FNV-1a");
-    
-    write_output_file(filename, &sorted, total_words, counts.len(), execution_time)?;
-    
+    println!(
+        "Tokenizer:       {}",
+        if stats.unicode {
+            "Unicode (single-threaded, slower than the ASCII fast path)"
+        } else {
+            "ASCII (fast path)"
+        }
+    );
+    if ran_parallel {
+        println!("Threads:         {}", threads);
+    }
+    if stopwords.is_some() {
+        println!("Stopwords filtered: {}", format_number(stats.filtered_words as u32));
+    }
+
+    write_output_file(&filenames, format, &sorted, &sorted_ngrams, &query_results, &stats)?;
+
     Ok(())
 }
 
+/// One queried word's standing in the full ranking, produced by `--words
+/// foo,bar,baz`. `rank` is `None` for a word absent from the counts (count
+/// 0): one never tokenized out of the input, or one removed as a stopword,
+/// mirroring MeiliSearch's words-frequencies
+/// command.
+struct WordQuery {
+    word: String,
+    count: u32,
+    document_frequency: u32,
+    percentage: f64,
+    rank: Option<usize>,
+}
+
+/// Looks up each word in `queries` against the already-computed `sorted`
+/// ranking, reusing `global_counts` (via `sorted`'s borrow) rather than
+/// re-scanning the input. Words never seen get count 0, no rank, and 0%.
+fn query_words(
+    queries: &[String],
+    counts: &FnvHashMap<String, WordStat>,
+    sorted: &[(&String, &WordStat)],
+    total_words: u64,
+) -> Vec<WordQuery> {
+    if queries.is_empty() {
+        return Vec::new();
+    }
+
+    let ranks: FnvHashMap<&str, usize> = sorted
+        .iter()
+        .enumerate()
+        .map(|(index, (word, _))| (word.as_str(), index + 1))
+        .collect();
+
+    queries
+        .iter()
+        .map(|word| {
+            let stat = counts.get(word).copied().unwrap_or((0, 0));
+            let percentage = if total_words > 0 {
+                (stat.0 as f64 * 100.0) / total_words as f64
+            } else {
+                0.0
+            };
+            WordQuery {
+                word: word.clone(),
+                count: stat.0,
+                document_frequency: stat.1,
+                percentage,
+                rank: ranks.get(word.as_str()).copied(),
+            }
+        })
+        .collect()
+}
+
+/// Per-chunk counting output. One of these comes out of `count_chunk` for
+/// the serial path and for each worker's slice in the parallel path; the
+/// per-thread results are then merged by summing counts for shared keys.
+#[derive(Default)]
+struct ChunkResult {
+    counts: FnvHashMap<String, u32>,
+    total_words: u64,
+    filtered_words: u64,
+    ngrams: FnvHashMap<Vec<String>, u32>,
+    total_sentences: u64,
+    total_syllables: u64,
+}
+
+/// Counts words (and, if `ngram_n` is given, word n-grams) in a single byte
+/// slice, the same logic used for both the serial path and each worker's
+/// chunk in the parallel path.
+///
+/// `total_words` is accounted pre-filter: every token found in the text
+/// counts towards it, stopword or not, so percentages elsewhere stay
+/// relative to the full text. Tokens matching `stopwords` are excluded from
+/// `counts` and tallied separately in `filtered_words`. N-grams are built
+/// from the raw token stream (before stopword filtering) by keeping a ring
+/// buffer of the last `ngram_n - 1` words.
+///
+/// In parallel mode each chunk starts its ring buffer empty, so a word
+/// n-gram that straddles a chunk boundary would be missed; this mirrors the
+/// boundary-safe unigram split, which is exact only because words (not
+/// n-grams) never cross a cut. The same boundary issue applies to
+/// `readability`'s sentence-terminator run state, which also resets at each
+/// chunk start. `main` avoids both by forcing serial execution whenever
+/// `--ngram` or `--readability` is combined with `--threads`, so
+/// `count_words_parallel` should not be called directly with `ngram_n` set
+/// or `readability` true.
+fn count_chunk(
+    bytes: &[u8],
+    stopwords: Option<&FnvHashSet<String>>,
+    ngram_n: Option<usize>,
+    readability: bool,
+) -> ChunkResult {
+    let mut result = ChunkResult {
+        counts: FnvHashMap::with_capacity_and_hasher(10_000, FnvBuildHasher::default()),
+        ..Default::default()
+    };
+
+    let mut current_word = Vec::with_capacity(100);
+    let mut window: VecDeque<String> = VecDeque::with_capacity(ngram_n.unwrap_or(1));
+    let mut in_terminator_run = false;
+
+    for &byte in bytes.iter() {
+        if byte.is_ascii_alphabetic() {
+            current_word.push(byte.to_ascii_lowercase());
+            in_terminator_run = false;
+        } else {
+            if !current_word.is_empty() {
+                let word = unsafe { String::from_utf8_unchecked(current_word.clone()) };
+                account_word(word, &mut result, stopwords, ngram_n, &mut window, readability);
+                current_word.clear();
+            }
+
+            if readability {
+                if matches!(byte, b'.' | b'!' | b'?') {
+                    if !in_terminator_run {
+                        result.total_sentences += 1;
+                    }
+                    in_terminator_run = true;
+                } else {
+                    in_terminator_run = false;
+                }
+            }
+        }
+    }
+
+    if !current_word.is_empty() {
+        let word = unsafe { String::from_utf8_unchecked(current_word) };
+        account_word(word, &mut result, stopwords, ngram_n, &mut window, readability);
+    }
+
+    result
+}
+
+/// Counts words the same way as `count_chunk`, but tokenizes Unicode text
+/// instead of ASCII bytes: a word is a maximal run of `char::is_alphabetic`
+/// characters (so accented and non-Latin scripts are kept whole instead of
+/// being chopped at the first non-ASCII byte), lowercased with `char`'s
+/// full Unicode lowercase mapping. This is an approximation of full UAX #29
+/// word-boundary segmentation, not a complete implementation, but it needs
+/// no external crate and handles the common "café" / "naïve" / "Götterfunken"
+/// cases the ASCII path drops.
+///
+/// Always runs single-threaded: splitting `text` at arbitrary char
+/// boundaries for `--threads` would need UTF-8-aware boundary search, which
+/// isn't implemented, so Unicode mode trades throughput for correctness.
+fn count_chunk_unicode(
+    text: &str,
+    stopwords: Option<&FnvHashSet<String>>,
+    ngram_n: Option<usize>,
+    readability: bool,
+) -> ChunkResult {
+    let mut result = ChunkResult {
+        counts: FnvHashMap::with_capacity_and_hasher(10_000, FnvBuildHasher::default()),
+        ..Default::default()
+    };
+
+    let mut current_word = String::with_capacity(100);
+    let mut window: VecDeque<String> = VecDeque::with_capacity(ngram_n.unwrap_or(1));
+    let mut in_terminator_run = false;
+
+    for ch in text.chars() {
+        if ch.is_alphabetic() {
+            current_word.extend(ch.to_lowercase());
+            in_terminator_run = false;
+        } else {
+            if !current_word.is_empty() {
+                account_word(
+                    std::mem::take(&mut current_word),
+                    &mut result,
+                    stopwords,
+                    ngram_n,
+                    &mut window,
+                    readability,
+                );
+            }
+
+            if readability {
+                if matches!(ch, '.' | '!' | '?') {
+                    if !in_terminator_run {
+                        result.total_sentences += 1;
+                    }
+                    in_terminator_run = true;
+                } else {
+                    in_terminator_run = false;
+                }
+            }
+        }
+    }
+
+    if !current_word.is_empty() {
+        account_word(current_word, &mut result, stopwords, ngram_n, &mut window, readability);
+    }
+
+    result
+}
+
+/// Folds one emitted word into a `ChunkResult`: tallies it (unless it's a
+/// stopword), extends the n-gram ring buffer, and accrues its syllable
+/// estimate when `readability` is on. Shared by the ASCII and Unicode
+/// tokenizer loops, which differ only in how they split `word`s out of the
+/// input.
+fn account_word(
+    word: String,
+    result: &mut ChunkResult,
+    stopwords: Option<&FnvHashSet<String>>,
+    ngram_n: Option<usize>,
+    window: &mut VecDeque<String>,
+    readability: bool,
+) {
+    result.total_words += 1;
+
+    if readability {
+        result.total_syllables += estimate_syllables(&word) as u64;
+    }
+
+    if let Some(n) = ngram_n {
+        if n >= 2 {
+            if window.len() == n - 1 {
+                let mut key: Vec<String> = window.iter().cloned().collect();
+                key.push(word.clone());
+                *result.ngrams.entry(key).or_insert(0) += 1;
+            }
+            window.push_back(word.clone());
+            if window.len() > n - 1 {
+                window.pop_front();
+            }
+        }
+    }
+
+    if stopwords.is_some_and(|sw| sw.contains(&word)) {
+        result.filtered_words += 1;
+    } else {
+        *result.counts.entry(word).or_insert(0) += 1;
+    }
+}
+
+/// Splits `buffer` into `threads` byte ranges, nudging each cut point forward
+/// to the next non-alphabetic byte so no word is split across a boundary.
+fn chunk_boundaries(buffer: &[u8], threads: usize) -> Vec<usize> {
+    let len = buffer.len();
+    let mut bounds = Vec::with_capacity(threads + 1);
+    bounds.push(0);
+
+    for t in 1..threads {
+        let mut cut = (len * t) / threads;
+        while cut < len && buffer[cut].is_ascii_alphabetic() {
+            cut += 1;
+        }
+        bounds.push(cut);
+    }
+
+    bounds.push(len);
+    bounds
+}
+
+/// Runs `count_chunk` on `threads` roughly equal, boundary-safe slices of
+/// `buffer` and merges the per-thread maps by summing counts for shared keys.
+/// The merged word counts, `total_words` and `filtered_words` are
+/// bit-for-bit identical to the serial `count_chunk` path before sorting.
+/// N-grams and sentence counts are not: with `ngram_n` set an n-gram that
+/// straddles a cut is missed, and with `readability` on a terminator run
+/// that straddles a cut is counted twice. `main` never calls this with
+/// either enabled.
+fn count_words_parallel(
+    buffer: &[u8],
+    threads: usize,
+    stopwords: Option<&FnvHashSet<String>>,
+    ngram_n: Option<usize>,
+    readability: bool,
+) -> ChunkResult {
+    let bounds = chunk_boundaries(buffer, threads);
+
+    let chunk_results: Vec<ChunkResult> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|t| {
+                let slice = &buffer[bounds[t]..bounds[t + 1]];
+                scope.spawn(move || count_chunk(slice, stopwords, ngram_n, readability))
+            })
+            .collect();
+
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    let mut merged = ChunkResult {
+        counts: FnvHashMap::with_capacity_and_hasher(10_000, FnvBuildHasher::default()),
+        ..Default::default()
+    };
+
+    for chunk in chunk_results {
+        merged.total_words += chunk.total_words;
+        merged.filtered_words += chunk.filtered_words;
+        merged.total_sentences += chunk.total_sentences;
+        merged.total_syllables += chunk.total_syllables;
+        for (word, count) in chunk.counts {
+            *merged.counts.entry(word).or_insert(0) += count;
+        }
+        for (pair, count) in chunk.ngrams {
+            *merged.ngrams.entry(pair).or_insert(0) += count;
+        }
+    }
+
+    merged
+}
+
+/// Builds the active stopword set, if any, from a built-in `--stopwords
+/// <lang>` list and/or a custom `--stopwords-file <path>` (newline-delimited,
+/// one word per line). Both may be given together; their entries are merged.
+fn load_stopwords(lang: Option<&str>, file: Option<&str>) -> io::Result<Option<FnvHashSet<String>>> {
+    if lang.is_none() && file.is_none() {
+        return Ok(None);
+    }
+
+    let mut set: FnvHashSet<String> = FnvHashSet::default();
+
+    if let Some(lang) = lang {
+        match lang {
+            "english" => set.extend(ENGLISH_STOPWORDS.iter().map(|s| s.to_string())),
+            other => {
+                eprintln!("Error: unknown stopwords language '{}'", other);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if let Some(path) = file {
+        let reader = BufReader::new(File::open(path)?);
+        for line in reader.lines() {
+            let word = line?.trim().to_ascii_lowercase();
+            if !word.is_empty() {
+                set.insert(word);
+            }
+        }
+    }
+
+    Ok(Some(set))
+}
+
 fn format_number(n: u32) -> String {
     let s = n.to_string();
     let mut result = String::new();
@@ -127,41 +761,328 @@ fn format_number(n: u32) -> String {
 }
 
 fn write_output_file(
-    input_filename: &str,
-    sorted: &[(&String, &u32)],
-    total_words: u64,
-    unique_words: usize,
-    execution_time: f64,
+    input_filenames: &[String],
+    format: OutputFormat,
+    sorted: &[(&String, &WordStat)],
+    sorted_ngrams: &[(&Vec<String>, &u32)],
+    query_results: &[WordQuery],
+    stats: &Stats,
 ) -> io::Result<()> {
-    let output_filename = input_filename
-        .rsplit_once('.')
-        .map(|(base, _)| format!("{}_rust_results.txt", base))
-        .unwrap_or_else(|| format!("{}_rust_results.txt", input_filename));
-    
+    let extension = format.extension();
+    let output_filename = match input_filenames {
+        [single] => single
+            .rsplit_once('.')
+            .map(|(base, _)| format!("{}_rust_results.{}", base, extension))
+            .unwrap_or_else(|| format!("{}_rust_results.{}", single, extension)),
+        _ => format!("combined_rust_results.{}", extension),
+    };
+
     let file = File::create(&output_filename)?;
     let mut writer = BufWriter::new(file);
-    
+
+    match format {
+        OutputFormat::Text => write_text_output(
+            &mut writer,
+            input_filenames,
+            sorted,
+            sorted_ngrams,
+            query_results,
+            stats,
+        )?,
+        OutputFormat::Json => {
+            write_json_output(&mut writer, sorted, sorted_ngrams, query_results, stats)?
+        }
+        OutputFormat::Csv => {
+            write_csv_output(&mut writer, sorted, sorted_ngrams, query_results, stats)?
+        }
+    }
+
+    println!("\nResults written to: {}", output_filename);
+    Ok(())
+}
+
+fn write_text_output(
+    writer: &mut impl Write,
+    input_filenames: &[String],
+    sorted: &[(&String, &WordStat)],
+    sorted_ngrams: &[(&Vec<String>, &u32)],
+    query_results: &[WordQuery],
+    stats: &Stats,
+) -> io::Result<()> {
     writeln!(writer, "Word Frequency Analysis - Rust Implementation")?;
-    writeln!(writer, "Input file: {}", input_filename)?;
-    writeln!(writer, "Execution time: {:.2} ms\n", execution_time)?;
-    writeln!(writer, "Total words: {}", format_number(total_words as u32))?;
-    writeln!(writer, "Unique words: {}\n", format_number(unique_words as u32))?;
+    writeln!(writer, "Input files: {}", input_filenames.join(", "))?;
+    writeln!(writer, "Execution time: {:.2} ms\n", stats.execution_time_ms)?;
+    writeln!(writer, "Total words: {}", format_number(stats.total_words as u32))?;
+    writeln!(writer, "Unique words: {}", format_number(stats.unique_words as u32))?;
+    if stats.filtered_words > 0 {
+        writeln!(writer, "Stopwords filtered: {}", format_number(stats.filtered_words as u32))?;
+    }
+    if let Some(r) = &stats.readability {
+        writeln!(writer, "Sentences: {}", format_number(r.sentences as u32))?;
+        writeln!(writer, "Syllables: {}", format_number(r.syllables as u32))?;
+        writeln!(writer, "Flesch Reading Ease: {:.2}", r.flesch_reading_ease)?;
+        writeln!(writer, "Flesch-Kincaid Grade: {:.2}", r.flesch_kincaid_grade)?;
+    }
+    writeln!(writer)?;
     writeln!(writer, "Top 100 Most Frequent Words:")?;
-    writeln!(writer, "Rank  Word            Count     Percentage")?;
-    writeln!(writer, "----  --------------- --------- ----------")?;
-    
-    for (index, (word, count)) in sorted.iter().take(100).enumerate() {
-        let percentage = (**count as f64 * 100.0) / total_words as f64;
+    writeln!(writer, "Rank  Word            Count     DocFreq  Percentage")?;
+    writeln!(writer, "----  --------------- --------- -------- ----------")?;
+
+    for (index, (word, stat)) in sorted.iter().take(100).enumerate() {
+        let percentage = (stat.0 as f64 * 100.0) / stats.total_words as f64;
         writeln!(
             writer,
-            "{:4}  {:<15} {:>9} {:>10.2}%",
+            "{:4}  {:<15} {:>9} {:>8} {:>10.2}%",
             index + 1,
             word,
-            format_number(**count),
+            format_number(stat.0),
+            stat.1,
             percentage
         )?;
     }
-    
-    println!("\nResults written to: {}", output_filename);
+
+    if !sorted_ngrams.is_empty() {
+        writeln!(writer)?;
+        writeln!(writer, "Top 100 Word Pairs:")?;
+        writeln!(writer, "Rank  Pair                      Count")?;
+        writeln!(writer, "----  ------------------------- ---------")?;
+        for (index, (words, count)) in sorted_ngrams.iter().take(100).enumerate() {
+            writeln!(
+                writer,
+                "{:4}  {:<25} {:>9}",
+                index + 1,
+                words.join(" "),
+                format_number(**count)
+            )?;
+        }
+    }
+
+    if !query_results.is_empty() {
+        writeln!(writer)?;
+        writeln!(writer, "Word Query:")?;
+        writeln!(writer, "Word            Count     DocFreq  Percentage  Rank")?;
+        writeln!(writer, "--------------- --------- -------- ----------  ----------")?;
+        for q in query_results {
+            let rank = q.rank.map(|r| r.to_string()).unwrap_or_else(|| "not found".to_string());
+            writeln!(
+                writer,
+                "{:<15} {:>9} {:>8} {:>10.2}%  {}",
+                q.word,
+                format_number(q.count),
+                q.document_frequency,
+                q.percentage,
+                rank
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Emits `{"word": {"count": .., "document_frequency": ..}, ..., "stats":
+/// {...}}` by hand, with no external JSON crate, escaping quotes/backslashes/
+/// control chars per the JSON spec.
+fn write_json_output(
+    writer: &mut impl Write,
+    sorted: &[(&String, &WordStat)],
+    sorted_ngrams: &[(&Vec<String>, &u32)],
+    query_results: &[WordQuery],
+    stats: &Stats,
+) -> io::Result<()> {
+    let mut entries: Vec<String> = sorted
+        .iter()
+        .map(|(word, stat)| {
+            format!(
+                "  \"{}\": {{\"count\": {}, \"document_frequency\": {}}}",
+                json_escape(word),
+                stat.0,
+                stat.1
+            )
+        })
+        .collect();
+
+    if !sorted_ngrams.is_empty() {
+        let ngram_entries: Vec<String> = sorted_ngrams
+            .iter()
+            .map(|(words, count)| {
+                let words_json: Vec<String> = words.iter().map(|w| format!("\"{}\"", json_escape(w))).collect();
+                format!("    {{\"words\": [{}], \"count\": {}}}", words_json.join(", "), count)
+            })
+            .collect();
+        entries.push(format!("  \"ngrams\": [\n{}\n  ]", ngram_entries.join(",\n")));
+    }
+
+    if !query_results.is_empty() {
+        let query_entries: Vec<String> = query_results
+            .iter()
+            .map(|q| {
+                let rank = q.rank.map(|r| r.to_string()).unwrap_or_else(|| "null".to_string());
+                format!(
+                    "    \"{}\": {{\"count\": {}, \"document_frequency\": {}, \"percentage\": {:.2}, \"rank\": {}}}",
+                    json_escape(&q.word),
+                    q.count,
+                    q.document_frequency,
+                    q.percentage,
+                    rank
+                )
+            })
+            .collect();
+        entries.push(format!("  \"query\": {{\n{}\n  }}", query_entries.join(",\n")));
+    }
+
+    let readability_field = match &stats.readability {
+        Some(r) => format!(
+            ",\n    \"readability\": {{\"sentences\": {}, \"syllables\": {}, \"flesch_reading_ease\": {:.2}, \"flesch_kincaid_grade\": {:.2}}}",
+            r.sentences, r.syllables, r.flesch_reading_ease, r.flesch_kincaid_grade
+        ),
+        None => String::new(),
+    };
+
+    entries.push(format!(
+        "  \"stats\": {{\n    \"file_count\": {},\n    \"total_words\": {},\n    \"unique_words\": {},\n    \"execution_time_ms\": {:.2},\n    \"filtered_words\": {}{}\n  }}",
+        stats.file_count, stats.total_words, stats.unique_words, stats.execution_time_ms, stats.filtered_words, readability_field
+    ));
+
+    writeln!(writer, "{{")?;
+    writeln!(writer, "{}", entries.join(",\n"))?;
+    writeln!(writer, "}}")?;
+
     Ok(())
 }
+
+/// Escapes a string for embedding in a JSON string literal (quotes are
+/// added by the caller). Handles the characters JSON requires escaping:
+/// `"`, `\`, and the ASCII control characters.
+fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn write_csv_output(
+    writer: &mut impl Write,
+    sorted: &[(&String, &WordStat)],
+    sorted_ngrams: &[(&Vec<String>, &u32)],
+    query_results: &[WordQuery],
+    stats: &Stats,
+) -> io::Result<()> {
+    writeln!(writer, "rank,word,count,document_frequency,percentage")?;
+
+    for (index, (word, stat)) in sorted.iter().enumerate() {
+        let percentage = (stat.0 as f64 * 100.0) / stats.total_words as f64;
+        writeln!(writer, "{},{},{},{},{:.2}", index + 1, word, stat.0, stat.1, percentage)?;
+    }
+
+    if !sorted_ngrams.is_empty() {
+        writeln!(writer)?;
+        writeln!(writer, "rank,pair,count")?;
+        for (index, (words, count)) in sorted_ngrams.iter().enumerate() {
+            writeln!(writer, "{},{},{}", index + 1, words.join(" "), count)?;
+        }
+    }
+
+    if let Some(r) = &stats.readability {
+        writeln!(writer)?;
+        writeln!(writer, "sentences,syllables,flesch_reading_ease,flesch_kincaid_grade")?;
+        writeln!(
+            writer,
+            "{},{},{:.2},{:.2}",
+            r.sentences, r.syllables, r.flesch_reading_ease, r.flesch_kincaid_grade
+        )?;
+    }
+
+    if !query_results.is_empty() {
+        writeln!(writer)?;
+        writeln!(writer, "word,count,document_frequency,percentage,rank")?;
+        for q in query_results {
+            let rank = q.rank.map(|r| r.to_string()).unwrap_or_else(|| "not found".to_string());
+            writeln!(
+                writer,
+                "{},{},{},{:.2},{}",
+                q.word, q.count, q.document_frequency, q.percentage, rank
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The merged parallel result must match the serial result exactly:
+    /// same counts, same `total_words`, regardless of how many threads cut
+    /// the buffer up. `ngram_n` is deliberately left `None` here since
+    /// `count_words_parallel` isn't meant to be called with ngrams set
+    /// directly (see its doc comment and `count_chunk`'s); `main` forces the serial path for
+    /// that combination instead.
+    #[test]
+    fn parallel_merge_matches_serial() {
+        let text = "the quick brown fox jumps over the lazy dog the dog barks \
+                     the cat runs the dog sleeps the fox hides the quick cat jumps"
+            .repeat(50);
+        let buffer = text.as_bytes();
+
+        let serial = count_chunk(buffer, None, None, false);
+        let parallel = count_words_parallel(buffer, 8, None, None, false);
+
+        assert_eq!(serial.total_words, parallel.total_words);
+
+        let mut serial_counts: Vec<(&String, &u32)> = serial.counts.iter().collect();
+        let mut parallel_counts: Vec<(&String, &u32)> = parallel.counts.iter().collect();
+        serial_counts.sort_unstable_by_key(|(word, _)| (*word).clone());
+        parallel_counts.sort_unstable_by_key(|(word, _)| (*word).clone());
+        assert_eq!(serial_counts, parallel_counts);
+    }
+
+    /// `chunk_boundaries` must never cut inside a word: every interior
+    /// boundary either lands at the end of the buffer or on a non-alphabetic
+    /// byte.
+    #[test]
+    fn chunk_boundaries_never_split_a_word() {
+        let text = "supercalifragilisticexpialidocious and other long words here ".repeat(30);
+        let buffer = text.as_bytes();
+
+        for threads in 2..=8 {
+            let bounds = chunk_boundaries(buffer, threads);
+            for &cut in &bounds[1..bounds.len() - 1] {
+                assert!(
+                    !buffer[cut].is_ascii_alphabetic(),
+                    "boundary at {} lands mid-word for {} threads",
+                    cut,
+                    threads
+                );
+            }
+        }
+    }
+
+    /// Syllable heuristic: one group of consecutive vowels counts as one
+    /// syllable, minus one for a silent trailing "e", clamped to at least 1.
+    #[test]
+    fn estimate_syllables_basic_cases() {
+        assert_eq!(estimate_syllables("cat"), 1);
+        assert_eq!(estimate_syllables("hello"), 2);
+        assert_eq!(estimate_syllables("apple"), 1);
+        assert_eq!(estimate_syllables("the"), 1);
+    }
+
+    #[test]
+    fn json_escape_handles_special_characters() {
+        assert_eq!(json_escape("plain"), "plain");
+        assert_eq!(json_escape("quote\"here"), "quote\\\"here");
+        assert_eq!(json_escape("back\\slash"), "back\\\\slash");
+        assert_eq!(json_escape("line\nbreak"), "line\\nbreak");
+        assert_eq!(json_escape("tab\there"), "tab\\there");
+    }
+}